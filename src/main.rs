@@ -45,6 +45,7 @@
 
 use std::collections::HashMap;
 use rand::prelude::IndexedRandom;
+use serde::{Deserialize, Serialize};
 use commands::CommandHandler;
 use queries::QueryHandler;
 
@@ -61,21 +62,64 @@ pub enum ShortenerError {
     /// This error occurs when the provided [`Slug`] does not map to any existing
     /// short link.
     SlugNotFound,
+
+    /// This error occurs when a random slug could not be generated after
+    /// [`UrlShortenerService::MAX_SLUG_GEN_ATTEMPTS`] tries, meaning the
+    /// configured keyspace (alphabet and length) is nearly exhausted.
+    ///
+    /// [`UrlShortenerService::MAX_SLUG_GEN_ATTEMPTS`]: super::UrlShortenerService
+    SlugSpaceExhausted,
+
+    /// This error occurs when [`UrlShortenerService::from_snapshot`] is given
+    /// a snapshot and tail whose combined length doesn't match the expected
+    /// total event count, meaning the reconstruction would be stale.
+    ///
+    /// [`UrlShortenerService::from_snapshot`]: super::UrlShortenerService
+    StaleSnapshot,
+
+    /// This error occurs when [`UrlShortenerService::load_events_json`] is
+    /// given a string that isn't a valid JSON array of [`events::Event`]s.
+    InvalidEventLog,
+
+    /// This error occurs when [`UrlShortenerService::export_events`] is
+    /// called on a service that was rebuilt via
+    /// [`UrlShortenerService::from_snapshot`]: the events folded into the
+    /// snapshot are gone, so only a partial, corrupt log could be exported.
+    ///
+    /// [`UrlShortenerService::export_events`]: super::UrlShortenerService
+    /// [`UrlShortenerService::from_snapshot`]: super::UrlShortenerService
+    IncompleteEventLog,
+
+    /// This error occurs when [`CommandHandler::handle_redirect`] is called
+    /// on a [`ShortLink`] whose `expires_at` is in the past.
+    ///
+    /// [`CommandHandler::handle_redirect`]: super::commands::CommandHandler
+    LinkExpired,
+
+    /// This error occurs when [`UrlShortenerService::with_slug_config`] (or
+    /// [`UrlShortenerService::from_events_with_config`]) is given an empty
+    /// alphabet, which would make slug generation impossible.
+    ///
+    /// [`UrlShortenerService::with_slug_config`]: super::UrlShortenerService
+    /// [`UrlShortenerService::from_events_with_config`]: super::UrlShortenerService
+    EmptySlugAlphabet,
 }
 
 /// A unique string (or alias) that represents the shortened version of the
 /// URL.
-#[derive(Clone, Debug, Hash, PartialEq)]
+#[derive(Clone, Debug, Hash, PartialEq, Serialize, Deserialize)]
 pub struct Slug(pub String);
 
 impl Eq for Slug {}
 
 /// The original URL that the short link points to.
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, Hash, PartialEq, Serialize, Deserialize)]
 pub struct Url(pub String);
 
+impl Eq for Url {}
+
 /// Shortened URL representation.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ShortLink {
     /// A unique string (or alias) that represents the shortened version of the
     /// URL.
@@ -83,10 +127,14 @@ pub struct ShortLink {
 
     /// The original URL that the short link points to.
     pub url: Url,
+
+    /// Unix timestamp (seconds) after which the link stops redirecting.
+    /// `None` means the link never expires.
+    pub expires_at: Option<u64>,
 }
 
 /// Statistics of the [`ShortLink`].
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Stats {
     /// [`ShortLink`] to which this [`Stats`] are related.
     pub link: ShortLink,
@@ -144,82 +192,460 @@ pub mod queries {
     }
 }
 
+/// Event Sourcing primitives.
+pub mod events {
+    use serde::{Deserialize, Serialize};
+    use super::{Slug, Url};
+
+    /// A single fact recorded by the service. The full, ordered log of
+    /// [`Event`]s is the source of truth: the in-memory projections used to
+    /// answer commands and queries are always derivable by folding
+    /// [`UrlShortenerService::apply`] over this log.
+    ///
+    /// [`UrlShortenerService::apply`]: super::UrlShortenerService
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    pub enum Event {
+        /// A new [`ShortLink`] was created for `slug` pointing at `url`,
+        /// optionally expiring at `expires_at` (unix timestamp, seconds).
+        ///
+        /// `claims_dedup` records whether `slug` should become (or keep
+        /// being) the reverse-index target for `url`, decided once at
+        /// command time: `false` means `url` already had a different, still
+        /// live slug when this one was created, so the dedup index must
+        /// keep pointing at that one instead. Baking the decision into the
+        /// event (rather than re-deriving it from the current clock while
+        /// folding) keeps replay a pure function of the event log.
+        ///
+        /// [`ShortLink`]: super::ShortLink
+        ShortLinkCreated { slug: Slug, url: Url, expires_at: Option<u64>, claims_dedup: bool },
+
+        /// A redirect through `slug` was served.
+        RedirectLogged { slug: Slug },
+
+        /// The target `url` of an existing `slug` was changed to `new_url`.
+        ///
+        /// See `ShortLinkCreated::claims_dedup` for why `claims_dedup` is
+        /// decided at command time rather than during replay.
+        ShortLinkUrlChanged { slug: Slug, new_url: Url, claims_dedup: bool },
+    }
+}
+
+/// Provides the current time to the service, so that time-dependent
+/// decisions (like link expiry) stay deterministic under event replay: folding
+/// the same events under the same clock always yields the same outcome.
+pub trait Clock {
+    /// Returns the current unix timestamp, in seconds.
+    fn now(&self) -> u64;
+}
+
+/// The default [`Clock`], backed by [`std::time::SystemTime`].
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs()
+    }
+}
+
 /// CQRS and Event Sourcing-based service implementation
 pub struct UrlShortenerService {
     map: HashMap<Slug, ShortLink>,
     stats: HashMap<Slug, Stats>,
     slug_alphabet: Vec<char>,
+    slug_len: usize,
+    events: Vec<events::Event>,
+    // Reverse index from the already-shortened Url to its Slug, so that
+    // re-shortening the same long Url is an O(1) lookup instead of a linear
+    // scan over `map`.
+    url_index: HashMap<Url, Slug>,
+    // Number of events that were folded into `map`/`stats`/`url_index`
+    // before the start of `events`, i.e. the count of events a snapshot
+    // already accounted for. Zero unless this service was rebuilt with
+    // `from_snapshot`.
+    event_offset: usize,
+    clock: Box<dyn Clock>,
+}
+
+/// A point-in-time capture of a [`UrlShortenerService`]'s projections,
+/// produced by [`UrlShortenerService::snapshot`] and consumed by
+/// [`UrlShortenerService::from_snapshot`] to bound event-replay cost to the
+/// events recorded since the snapshot was taken.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    map: HashMap<Slug, ShortLink>,
+    stats: HashMap<Slug, Stats>,
+    url_index: HashMap<Url, Slug>,
+    slug_alphabet: Vec<char>,
+    slug_len: usize,
+
+    /// The number of events folded into this snapshot, i.e. the index one
+    /// past the last event it accounts for.
+    last_event_index: usize,
 }
 
 impl UrlShortenerService {
-    /// Creates a new instance of the service
+    /// The default, nanoid-inspired slug alphabet: unambiguous letters in
+    /// both cases plus digits.
+    const DEFAULT_SLUG_ALPHABET: &'static str =
+        "aAbBcCdDeEfFgGhHjJkKmMnNpPqQrRsStTuUvVwWxXyYzZ0123456789";
+
+    /// The default slug length, matching the original `choose_multiple(_, 6)`
+    /// behaviour.
+    const DEFAULT_SLUG_LEN: usize = 6;
+
+    /// How many times [`Self::generate_unique_slug`] retries before giving up
+    /// with [`ShortenerError::SlugSpaceExhausted`].
+    const MAX_SLUG_GEN_ATTEMPTS: usize = 32;
+
+    /// Creates a new instance of the service, using the default slug length
+    /// and alphabet.
     pub fn new() -> Self {
-        Self {
+        Self::with_slug_config(Self::DEFAULT_SLUG_LEN, Self::DEFAULT_SLUG_ALPHABET)
+            .expect("the default slug alphabet is non-empty")
+    }
+
+    /// Creates a new instance of the service that generates slugs of `len`
+    /// characters sampled (with replacement, nanoid-style) from `alphabet`.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`ShortenerError::EmptySlugAlphabet`] if `alphabet` is empty,
+    /// since [`Self::generate_unique_slug`] would otherwise have no
+    /// characters to sample from.
+    pub fn with_slug_config(len: usize, alphabet: &str) -> Result<Self, ShortenerError> {
+        if alphabet.is_empty() {
+            return Err(ShortenerError::EmptySlugAlphabet);
+        }
+
+        Ok(Self {
             map: HashMap::new(),
             stats: HashMap::new(),
-            slug_alphabet: "aAbBcCdDeEfFgGhHjJkKmMnNpPqQrRsStTuUvVwWxXyYzZ0123456789".chars().collect(),
+            slug_alphabet: alphabet.chars().collect(),
+            slug_len: len,
+            events: Vec::new(),
+            url_index: HashMap::new(),
+            event_offset: 0,
+            clock: Box::new(SystemClock),
+        })
+    }
+
+    /// Replaces the [`Clock`] used for expiry decisions, e.g. with a fake
+    /// clock in tests so replaying the same events deterministically
+    /// reproduces the same expiry outcome.
+    pub fn set_clock(&mut self, clock: impl Clock + 'static) {
+        self.clock = Box::new(clock);
+    }
+
+    /// Rebuilds a [`UrlShortenerService`] by folding `events` over an empty
+    /// state, in order, using the default slug length and alphabet. This is
+    /// the deterministic-replay counterpart to the event log recorded by
+    /// every [`CommandHandler`] method.
+    ///
+    /// If the original service was built with [`Self::with_slug_config`],
+    /// use [`Self::from_events_with_config`] instead so future slug
+    /// generation keeps using that configured keyspace.
+    pub fn from_events(events: impl IntoIterator<Item = events::Event>) -> Self {
+        Self::from_events_with_config(events, Self::DEFAULT_SLUG_LEN, Self::DEFAULT_SLUG_ALPHABET)
+            .expect("the default slug alphabet is non-empty")
+    }
+
+    /// Like [`Self::from_events`], but rebuilds a service configured with
+    /// `len`/`alphabet` for future slug generation, matching whatever was
+    /// originally passed to [`Self::with_slug_config`].
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`ShortenerError::EmptySlugAlphabet`] if `alphabet` is empty;
+    /// see [`Self::with_slug_config`].
+    pub fn from_events_with_config(
+        events: impl IntoIterator<Item = events::Event>,
+        len: usize,
+        alphabet: &str,
+    ) -> Result<Self, ShortenerError> {
+        let mut svc = Self::with_slug_config(len, alphabet)?;
+
+        for event in events {
+            svc.apply(&event);
+            svc.events.push(event);
+        }
+
+        Ok(svc)
+    }
+
+    /// Returns a copy of the full recorded event log, suitable for
+    /// persisting (e.g. via its JSON counterpart, [`Self::load_events_json`])
+    /// without any external store.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`ShortenerError::IncompleteEventLog`] if this service was
+    /// rebuilt via [`Self::from_snapshot`]: the events folded into the
+    /// snapshot aren't retained, so `self.events` alone is only a tail, not
+    /// the full log, and exporting it would silently produce a log that
+    /// can't reconstruct the current state. Export from the service that
+    /// took the snapshot instead, or keep persisting its full event log
+    /// externally across snapshots.
+    pub fn export_events(&self) -> Result<Vec<events::Event>, ShortenerError> {
+        if self.event_offset != 0 {
+            return Err(ShortenerError::IncompleteEventLog);
+        }
+
+        Ok(self.events.clone())
+    }
+
+    /// Rebuilds a [`UrlShortenerService`] from a JSON array of
+    /// [`events::Event`]s previously produced by [`Self::export_events`],
+    /// e.g. read back from a file, stdin, or an embedded literal.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`ShortenerError::InvalidEventLog`] if `json` is not a valid
+    /// JSON array of [`events::Event`]s.
+    pub fn load_events_json(json: &str) -> Result<Self, ShortenerError> {
+        let events: Vec<events::Event> = serde_json::from_str(json)
+            .map_err(|_| ShortenerError::InvalidEventLog)?;
+
+        Ok(Self::from_events(events))
+    }
+
+    /// Captures the current projections (`map`, `stats`, `url_index`) along
+    /// with how many events produced them, so that later reconstruction can
+    /// skip straight to replaying only what happened since.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            map: self.map.clone(),
+            stats: self.stats.clone(),
+            url_index: self.url_index.clone(),
+            slug_alphabet: self.slug_alphabet.clone(),
+            slug_len: self.slug_len,
+            last_event_index: self.event_offset + self.events.len(),
+        }
+    }
+
+    /// Rebuilds a [`UrlShortenerService`] by seeding its projections from
+    /// `snapshot` and then folding only `tail`, the events recorded after the
+    /// snapshot was taken. This bounds replay cost to `tail.len()` instead of
+    /// the full event log.
+    ///
+    /// `total_events` is the authoritative length of the full event log (as
+    /// tracked by the event store the snapshot and tail were read from).
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`ShortenerError::StaleSnapshot`] if `snapshot`'s last event
+    /// index plus `tail`'s length doesn't add up to `total_events`, which
+    /// means `tail` is missing events, has extra events, or doesn't actually
+    /// continue from `snapshot` — reconstructing from it would silently
+    /// produce stale state.
+    pub fn from_snapshot(
+        snapshot: Snapshot,
+        tail: impl IntoIterator<Item = events::Event>,
+        total_events: usize,
+    ) -> Result<Self, ShortenerError> {
+        let tail: Vec<_> = tail.into_iter().collect();
+
+        if snapshot.last_event_index + tail.len() != total_events {
+            return Err(ShortenerError::StaleSnapshot);
+        }
+
+        let mut svc = Self {
+            map: snapshot.map,
+            stats: snapshot.stats,
+            url_index: snapshot.url_index,
+            slug_alphabet: snapshot.slug_alphabet,
+            slug_len: snapshot.slug_len,
+            events: Vec::new(),
+            event_offset: snapshot.last_event_index,
+            clock: Box::new(SystemClock),
+        };
+
+        for event in tail {
+            svc.apply(&event);
+            svc.events.push(event);
         }
+
+        Ok(svc)
     }
 
-    fn generate_unique_slug(&self) -> Slug {
+    /// The single place that mutates `map`/`stats` in response to an
+    /// [`events::Event`]. Applying the same event twice on top of the same
+    /// state always produces the same projections.
+    fn apply(&mut self, event: &events::Event) {
+        match event {
+            events::Event::ShortLinkCreated { slug, url, expires_at, claims_dedup } => {
+                let link = ShortLink { slug: slug.clone(), url: url.clone(), expires_at: *expires_at };
+                self.map.insert(slug.clone(), link.clone());
+
+                if *claims_dedup {
+                    self.url_index.insert(url.clone(), slug.clone());
+                }
+
+                // We create stats entry with 0 redirects here to avoid panics
+                // in handle_redirect() and get_stats().
+                self.stats.insert(slug.clone(), Stats { link, redirects: 0 });
+            }
+            events::Event::RedirectLogged { slug } => {
+                if let Some(stats) = self.stats.get_mut(slug) {
+                    stats.redirects += 1;
+                }
+            }
+            events::Event::ShortLinkUrlChanged { slug, new_url, claims_dedup } => {
+                if let Some(link) = self.map.get_mut(slug) {
+                    // Only drop the old Url's index entry if it belongs to
+                    // this slug; another live slug may still own it.
+                    if self.url_index.get(&link.url) == Some(slug) {
+                        self.url_index.remove(&link.url);
+                    }
+                    link.url = new_url.clone();
+
+                    if *claims_dedup {
+                        self.url_index.insert(new_url.clone(), slug.clone());
+                    }
+                }
+                if let Some(stats) = self.stats.get_mut(slug) {
+                    stats.link.url = new_url.clone();
+                }
+            }
+        }
+    }
+
+    /// Records `event` in the append-only log and immediately folds it into
+    /// the in-memory projections via [`Self::apply`].
+    fn record_event(&mut self, event: events::Event) {
+        self.apply(&event);
+        self.events.push(event);
+    }
+
+    /// Whether `link`'s `expires_at` is in the past, per the current clock.
+    fn is_expired(&self, link: &ShortLink) -> bool {
+        link.expires_at.is_some_and(|t| t < self.clock.now())
+    }
+
+    /// Whether `url` already has a different, still-live dedup entry, i.e.
+    /// whether a fresh [`events::Event::ShortLinkCreated`] or
+    /// [`events::Event::ShortLinkUrlChanged`] targeting `url` should leave
+    /// the reverse index alone instead of claiming it.
+    fn url_has_live_dedup_entry(&self, url: &Url) -> bool {
+        self.url_index
+            .get(url)
+            .and_then(|slug| self.map.get(slug))
+            .is_some_and(|link| !self.is_expired(link))
+    }
+
+    /// Samples a fresh, unused [`Slug`] of `slug_len` characters, each drawn
+    /// independently (with replacement) from `slug_alphabet`.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`ShortenerError::SlugSpaceExhausted`] if no unused slug was
+    /// found within [`Self::MAX_SLUG_GEN_ATTEMPTS`] tries, which signals that
+    /// the configured keyspace is nearly full rather than spinning forever.
+    fn generate_unique_slug(&self) -> Result<Slug, ShortenerError> {
         let mut rng = rand::thread_rng();
 
-        loop {
+        for _ in 0..Self::MAX_SLUG_GEN_ATTEMPTS {
             let slug = Slug(String::from_iter(
-                self.slug_alphabet.choose_multiple(&mut rng, 6)
+                (0..self.slug_len).map(|_| *self.slug_alphabet.choose(&mut rng).unwrap())
             ));
 
             if !self.map.contains_key(&slug) {
-                return slug;
+                return Ok(slug);
             }
         }
+
+        Err(ShortenerError::SlugSpaceExhausted)
     }
 }
 
-impl commands::CommandHandler for UrlShortenerService {
-
-    fn handle_create_short_link(
+impl UrlShortenerService {
+    /// Like [`CommandHandler::handle_create_short_link`], but additionally
+    /// accepts `expires_at`: a unix timestamp (seconds) after which
+    /// [`CommandHandler::handle_redirect`] refuses to serve the link.
+    ///
+    /// ## Errors
+    ///
+    /// See [`ShortenerError`].
+    ///
+    /// [`CommandHandler::handle_create_short_link`]: commands::CommandHandler::handle_create_short_link
+    /// [`CommandHandler::handle_redirect`]: commands::CommandHandler::handle_redirect
+    pub fn handle_create_short_link_with_expiry(
         &mut self,
         url: Url,
         slug: Option<Slug>,
+        expires_at: Option<u64>,
     ) -> Result<ShortLink, ShortenerError> {
 
         if !validate_url(&url) {
             return Err(ShortenerError::InvalidUrl);
         };
 
+        // No custom slug was requested: if this exact Url has already been
+        // shortened to a still-live slug, hand back that existing ShortLink
+        // instead of minting a second one for the same target. An expired
+        // slug is dead weight, not a usable short link, so fall through and
+        // mint a fresh one instead (overwriting the stale url_index entry).
+        if slug.is_none() && self.url_has_live_dedup_entry(&url) {
+            let existing = self.url_index.get(&url).unwrap();
+            return Ok(self.map.get(existing).unwrap().clone());
+        }
+
+        // Whether this creation should claim the dedup index for `url`: not
+        // when an explicit slug is requested for a Url that's already
+        // shortened to a different, still-live slug — otherwise the live
+        // slug would become unreachable via dedup while still serving
+        // redirects, growing the map unboundedly for popular URLs (the
+        // exact thing dedup exists to prevent). Decided once here, at
+        // command time, so replaying the resulting event is deterministic.
+        let claims_dedup = !self.url_has_live_dedup_entry(&url);
+
         // Generate new slug if not provided.
-        let slug = slug.unwrap_or_else(|| self.generate_unique_slug());
+        let slug = match slug {
+            Some(slug) => slug,
+            None => self.generate_unique_slug()?,
+        };
 
         // Slug must be unique.
         if self.map.contains_key(&slug) {
             return Err(ShortenerError::SlugAlreadyInUse);
         };
 
-        // We create stats entry with 0 redirects here to avoid panics in
-        // handle_redirect() and get_stats().
-        let link = ShortLink { slug: slug.clone(), url };
-        self.map.insert(slug.clone(), link.clone());
-        self.stats.insert(slug.clone(), Stats { link: link.clone(), redirects: 0 });
+        self.record_event(events::Event::ShortLinkCreated { slug: slug.clone(), url, expires_at, claims_dedup });
+
+        Ok(self.map.get(&slug).unwrap().clone())
+    }
+}
+
+impl commands::CommandHandler for UrlShortenerService {
 
-        Ok(link)
+    fn handle_create_short_link(
+        &mut self,
+        url: Url,
+        slug: Option<Slug>,
+    ) -> Result<ShortLink, ShortenerError> {
+        self.handle_create_short_link_with_expiry(url, slug, None)
     }
 
     fn handle_redirect(
         &mut self,
         slug: Slug,
     ) -> Result<ShortLink, ShortenerError> {
-        
-        if let Some(link) = self.map.get(&slug) {
-            self.stats.get_mut(&slug).unwrap()
-                .redirects += 1;
 
-            Ok(link.clone())
+        if let Some(link) = self.map.get(&slug).cloned() {
+            if self.is_expired(&link) {
+                return Err(ShortenerError::LinkExpired);
+            }
+
+            self.record_event(events::Event::RedirectLogged { slug });
+
+            Ok(link)
         } else {
             Err(ShortenerError::SlugNotFound)
         }
     }
-    
+
     /// Updates the [Url] of a [ShortLink] using a given [Slug].
     fn handle_change_short_link(
         &mut self,
@@ -231,13 +657,19 @@ impl commands::CommandHandler for UrlShortenerService {
             return Err(ShortenerError::InvalidUrl);
         };
 
-        match self.map.get_mut(&slug) {
-            Some(link) => {
-                link.url = new_url;
-                Ok(link.clone())
-            }
-            None => Err(ShortenerError::SlugNotFound),
+        if !self.map.contains_key(&slug) {
+            return Err(ShortenerError::SlugNotFound);
         }
+
+        // Same dedup-preserving rule as short link creation: don't steal the
+        // reverse index entry from a different slug that's already serving
+        // `new_url` live.
+        let claims_dedup = self.url_index.get(&new_url) == Some(&slug)
+            || !self.url_has_live_dedup_entry(&new_url);
+
+        self.record_event(events::Event::ShortLinkUrlChanged { slug: slug.clone(), new_url, claims_dedup });
+
+        Ok(self.map.get(&slug).unwrap().clone())
     }
 }
 
@@ -312,7 +744,7 @@ fn main() {
     let stats = svc.get_stats(link.slug).unwrap();
     println!("Redirect count for {} is: {}", stats.link.slug.0, stats.redirects);
 
-    let non_exists = svc.generate_unique_slug();
+    let non_exists = svc.generate_unique_slug().expect("slug generation");
     match svc.get_stats(non_exists.clone()) {
         Err(e) => println!("Error getting stats for non-existing Slug {}: {:?}", non_exists.0, e),
         Ok(s) => panic!()
@@ -330,4 +762,197 @@ mod test {
         assert_eq!(true, validate_url(&Url("http://ya.ru".to_string())));
         assert_eq!(false, validate_url(&Url("abc".to_string())));
     }
+
+    #[test]
+    fn from_events_replays_creation_redirect_and_url_change() {
+        let events = vec![
+            events::Event::ShortLinkCreated {
+                slug: Slug("a".to_string()),
+                url: Url("https://a.com".to_string()),
+                expires_at: None,
+                claims_dedup: true,
+            },
+            events::Event::RedirectLogged { slug: Slug("a".to_string()) },
+            events::Event::RedirectLogged { slug: Slug("a".to_string()) },
+            events::Event::ShortLinkUrlChanged {
+                slug: Slug("a".to_string()),
+                new_url: Url("https://b.com".to_string()),
+                claims_dedup: true,
+            },
+        ];
+
+        let svc = UrlShortenerService::from_events(events);
+
+        let stats = svc.get_stats(Slug("a".to_string())).unwrap();
+        assert_eq!(stats.redirects, 2);
+        assert_eq!(stats.link.url, Url("https://b.com".to_string()));
+    }
+
+    #[test]
+    fn reshortening_same_url_returns_existing_slug() {
+        let mut svc = UrlShortenerService::new();
+        let url = Url("https://docs.rs".to_string());
+
+        let first = svc.handle_create_short_link(url.clone(), None).unwrap();
+        let second = svc.handle_create_short_link(url, None).unwrap();
+
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn explicit_slug_for_an_already_dedup_d_url_keeps_the_original_dedup_entry() {
+        let mut svc = UrlShortenerService::new();
+        let url = Url("https://docs.rs".to_string());
+
+        let auto = svc.handle_create_short_link(url.clone(), None).unwrap();
+        let explicit = svc
+            .handle_create_short_link(url.clone(), Some(Slug("custom".to_string())))
+            .unwrap();
+
+        assert_ne!(auto.slug, explicit.slug);
+
+        // Both slugs still redirect, but re-shortening the same Url again
+        // (no explicit slug) must keep returning the original auto slug, not
+        // silently switch the dedup index over to "custom".
+        let reshortened = svc.handle_create_short_link(url, None).unwrap();
+        assert_eq!(reshortened.slug, auto.slug);
+    }
+
+    #[test]
+    fn changing_a_slugs_url_to_an_already_dedup_d_live_url_keeps_the_original_dedup_entry() {
+        let mut svc = UrlShortenerService::new();
+        let shared_url = Url("https://docs.rs".to_string());
+        let other_url = Url("https://example.com".to_string());
+
+        let live = svc.handle_create_short_link(shared_url.clone(), None).unwrap();
+        let moved = svc.handle_create_short_link(other_url, None).unwrap();
+
+        // Re-point `moved`'s slug at a Url that's already live-dedup'd to a
+        // different slug; the reverse index must keep pointing at the
+        // original, still-live slug rather than switching over to `moved`.
+        svc.handle_change_short_link(moved.slug, shared_url.clone()).unwrap();
+
+        let reshortened = svc.handle_create_short_link(shared_url, None).unwrap();
+        assert_eq!(reshortened.slug, live.slug);
+    }
+
+    #[test]
+    fn generate_unique_slug_exhausts_a_tiny_keyspace() {
+        let mut svc = UrlShortenerService::with_slug_config(1, "ab").unwrap();
+
+        // The keyspace has only 2 possible slugs ("a", "b"); fill it up.
+        svc.handle_create_short_link(Url("https://a.com".to_string()), Some(Slug("a".to_string()))).unwrap();
+        svc.handle_create_short_link(Url("https://b.com".to_string()), Some(Slug("b".to_string()))).unwrap();
+
+        let err = svc.handle_create_short_link(Url("https://c.com".to_string()), None).unwrap_err();
+        assert_eq!(err, ShortenerError::SlugSpaceExhausted);
+    }
+
+    #[test]
+    fn with_slug_config_rejects_an_empty_alphabet() {
+        let result = UrlShortenerService::with_slug_config(6, "");
+        assert!(matches!(result, Err(ShortenerError::EmptySlugAlphabet)));
+    }
+
+    #[test]
+    fn from_snapshot_rejects_a_mismatched_tail() {
+        let mut svc = UrlShortenerService::new();
+        svc.handle_create_short_link(Url("https://a.com".to_string()), Some(Slug("a".to_string()))).unwrap();
+
+        let snapshot = svc.snapshot();
+        svc.handle_redirect(Slug("a".to_string())).unwrap();
+        svc.handle_redirect(Slug("a".to_string())).unwrap();
+
+        let tail = vec![events::Event::RedirectLogged { slug: Slug("a".to_string()) }];
+        let wrong_total = 3; // snapshot (1) + tail (1) == 2, not 3.
+
+        let result = UrlShortenerService::from_snapshot(snapshot, tail, wrong_total);
+        assert!(matches!(result, Err(ShortenerError::StaleSnapshot)));
+    }
+
+    #[test]
+    fn from_snapshot_replays_only_the_tail_and_keeps_slug_config() {
+        let mut svc = UrlShortenerService::with_slug_config(4, "ab").unwrap();
+        svc.handle_create_short_link(Url("https://a.com".to_string()), Some(Slug("aaaa".to_string()))).unwrap();
+
+        let snapshot = svc.snapshot();
+        svc.handle_redirect(Slug("aaaa".to_string())).unwrap();
+
+        let tail = vec![events::Event::RedirectLogged { slug: Slug("aaaa".to_string()) }];
+        let mut rebuilt = UrlShortenerService::from_snapshot(snapshot, tail, 2).unwrap();
+
+        assert_eq!(rebuilt.get_stats(Slug("aaaa".to_string())).unwrap().redirects, 1);
+
+        // The configured keyspace (len 4, alphabet "ab") must survive reconstruction,
+        // instead of silently reverting to the default length/alphabet.
+        let link = rebuilt.handle_create_short_link(Url("https://fresh.com".to_string()), None).unwrap();
+        assert_eq!(link.slug.0.len(), 4);
+        assert!(link.slug.0.chars().all(|c| c == 'a' || c == 'b'));
+    }
+
+    #[test]
+    fn export_events_round_trips_through_json() {
+        let mut svc = UrlShortenerService::new();
+        svc.handle_create_short_link(Url("https://a.com".to_string()), Some(Slug("a".to_string()))).unwrap();
+        svc.handle_redirect(Slug("a".to_string())).unwrap();
+
+        let json = serde_json::to_string(&svc.export_events().unwrap()).unwrap();
+        let rebuilt = UrlShortenerService::load_events_json(&json).unwrap();
+
+        assert_eq!(
+            rebuilt.get_stats(Slug("a".to_string())).unwrap(),
+            svc.get_stats(Slug("a".to_string())).unwrap(),
+        );
+    }
+
+    #[test]
+    fn export_events_fails_after_snapshot_reconstruction() {
+        let mut svc = UrlShortenerService::new();
+        svc.handle_create_short_link(Url("https://a.com".to_string()), Some(Slug("a".to_string()))).unwrap();
+        let snapshot = svc.snapshot();
+
+        let rebuilt = UrlShortenerService::from_snapshot(snapshot, Vec::new(), 1).unwrap();
+
+        assert_eq!(rebuilt.export_events().unwrap_err(), ShortenerError::IncompleteEventLog);
+    }
+
+    /// A [`Clock`] fully controlled by the test, so expiry decisions are
+    /// reproducible instead of racing the real wall clock.
+    struct FakeClock(std::cell::Cell<u64>);
+
+    impl Clock for FakeClock {
+        fn now(&self) -> u64 {
+            self.0.get()
+        }
+    }
+
+    #[test]
+    fn handle_redirect_rejects_expired_link_without_counting_it() {
+        let mut svc = UrlShortenerService::new();
+        svc.set_clock(FakeClock(std::cell::Cell::new(100)));
+
+        svc.handle_create_short_link_with_expiry(
+            Url("https://a.com".to_string()),
+            Some(Slug("a".to_string())),
+            Some(50),
+        ).unwrap();
+
+        let err = svc.handle_redirect(Slug("a".to_string())).unwrap_err();
+        assert_eq!(err, ShortenerError::LinkExpired);
+        assert_eq!(svc.get_stats(Slug("a".to_string())).unwrap().redirects, 0);
+    }
+
+    #[test]
+    fn reshortening_after_expiry_mints_a_fresh_slug() {
+        let mut svc = UrlShortenerService::new();
+        svc.set_clock(FakeClock(std::cell::Cell::new(100)));
+        let url = Url("https://a.com".to_string());
+
+        let dead = svc.handle_create_short_link_with_expiry(url.clone(), None, Some(50)).unwrap();
+        assert_eq!(svc.handle_redirect(dead.slug).unwrap_err(), ShortenerError::LinkExpired);
+
+        let fresh = svc.handle_create_short_link(url, None).unwrap();
+        assert_eq!(fresh.expires_at, None);
+        assert!(svc.handle_redirect(fresh.slug).is_ok());
+    }
 }
\ No newline at end of file